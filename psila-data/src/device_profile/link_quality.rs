@@ -1,8 +1,13 @@
 use core::convert::TryFrom;
 
+use zerocopy::{AsBytes, FromBytes, LayoutVerified, Unaligned};
+
 use crate::common::address::{ExtendedAddress, NetworkAddress};
 use crate::device_profile::Status;
 use crate::pack::{Pack, PackFixed};
+use crate::packet_view::{check_bounded_len, BoundedEntries};
+#[cfg(feature = "pretty-print")]
+use crate::pretty_print::{write_indent, PrettyPrint};
 use crate::Error;
 
 extended_enum!(
@@ -36,6 +41,25 @@ extended_enum!(
     Unknown => 0x02,
 );
 
+/// Size, in bytes, of the fixed-layout neighbor table entry.
+const NEIGHBOR_SIZE: usize = 22;
+
+/// Byte-for-byte layout of a packed neighbor table entry. Deriving
+/// `FromBytes`/`AsBytes`/`Unaligned` lets the fixed address blocks and
+/// bit-packed flag bytes be reinterpreted in place instead of indexed
+/// by hand.
+#[derive(FromBytes, AsBytes, Unaligned, Copy, Clone)]
+#[repr(C)]
+struct NeighborLayout {
+    pan_identifier: [u8; 8],
+    extended_address: [u8; 8],
+    network_address: [u8; 2],
+    flags: u8,
+    permit_joining: u8,
+    depth: u8,
+    link_quality: u8,
+}
+
 // 2.4.3.1.1 NWK_addr_req
 /// Network address request
 /// Requests the network address for a remote device
@@ -53,21 +77,31 @@ pub struct Neighbor {
 }
 
 impl Pack<Neighbor, Error> for Neighbor {
-    fn pack(&self, _data: &mut [u8]) -> Result<usize, Error> {
-        unimplemented!();
+    fn pack(&self, data: &mut [u8]) -> Result<usize, Error> {
+        let (mut layout, _): (LayoutVerified<&mut [u8], NeighborLayout>, &mut [u8]) =
+            LayoutVerified::new_from_prefix(data).ok_or(Error::WrongNumberOfBytes)?;
+        self.pan_identifier.pack(&mut layout.pan_identifier[..])?;
+        self.extended_address.pack(&mut layout.extended_address[..])?;
+        self.network_address.pack(&mut layout.network_address[..])?;
+        layout.flags = u8::from(self.device_type)
+            | (u8::from(self.rx_idle) << 2)
+            | (u8::from(self.relationship) << 4);
+        layout.permit_joining = u8::from(self.permit_joining) << 6;
+        layout.depth = self.depth;
+        layout.link_quality = self.link_quality;
+        Ok(NEIGHBOR_SIZE)
     }
 
     fn unpack(data: &[u8]) -> Result<(Self, usize), Error> {
-        if data.len() < 22 {
-            return Err(Error::WrongNumberOfBytes);
-        }
-        let pan_identifier = ExtendedAddress::unpack(&data[0..8])?;
-        let extended_address = ExtendedAddress::unpack(&data[8..16])?;
-        let network_address = NetworkAddress::unpack(&data[16..18])?;
-        let device_type = DeviceType::try_from(data[18] & 0b0000_0011)?;
-        let rx_idle = RxOnWhenIdle::try_from((data[18] & 0b0000_1100) >> 2)?;
-        let relationship = Relationship::try_from((data[18] & 0b0111_0000) >> 4)?;
-        let permit_joining = PermitJoining::try_from((data[19] & 0b1100_0000) >> 6)?;
+        let (layout, _): (LayoutVerified<&[u8], NeighborLayout>, &[u8]) =
+            LayoutVerified::new_from_prefix(data).ok_or(Error::WrongNumberOfBytes)?;
+        let pan_identifier = ExtendedAddress::unpack(&layout.pan_identifier[..])?;
+        let extended_address = ExtendedAddress::unpack(&layout.extended_address[..])?;
+        let network_address = NetworkAddress::unpack(&layout.network_address[..])?;
+        let device_type = DeviceType::try_from(layout.flags & 0b0000_0011)?;
+        let rx_idle = RxOnWhenIdle::try_from((layout.flags & 0b0000_1100) >> 2)?;
+        let relationship = Relationship::try_from((layout.flags & 0b0111_0000) >> 4)?;
+        let permit_joining = PermitJoining::try_from((layout.permit_joining & 0b1100_0000) >> 6)?;
         Ok((
             Self {
                 pan_identifier,
@@ -77,14 +111,119 @@ impl Pack<Neighbor, Error> for Neighbor {
                 rx_idle,
                 relationship,
                 permit_joining,
-                depth: data[20],
-                link_quality: data[21],
+                depth: layout.depth,
+                link_quality: layout.link_quality,
             },
-            22,
+            NEIGHBOR_SIZE,
         ))
     }
 }
 
+/// `Neighbor::unpack` returns the number of bytes consumed alongside
+/// the value, which doesn't match the `BoundedEntries` decode
+/// signature; this adapts it to a plain fixed-size decode.
+fn decode_neighbor(data: &[u8]) -> Result<Neighbor, Error> {
+    Neighbor::unpack(data).map(|(neighbor, _)| neighbor)
+}
+
+/// A borrowed, zero-copy view over a management LQI response payload.
+///
+/// Unlike [`ManagementLinkQualityIndicatorResponse`], this does not
+/// allocate a `Vec` of neighbors up front; [`neighbor`](Self::neighbor)
+/// and [`neighbors`](Self::neighbors) decode entries from the
+/// underlying buffer on demand.
+#[derive(Copy, Clone, Debug)]
+pub struct LinkQualityResponsePacket<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+/// Allocation-free iterator over the neighbors of a
+/// [`LinkQualityResponsePacket`].
+pub type NeighborIter<'a> = BoundedEntries<'a, Neighbor>;
+
+impl<T: AsRef<[u8]>> LinkQualityResponsePacket<T> {
+    /// Wrap `buffer` without checking that it holds a well-formed packet.
+    pub fn new_unchecked(buffer: T) -> Self {
+        Self { buffer }
+    }
+
+    /// Wrap `buffer`, checking that it is long enough to hold the
+    /// neighbor count encoded in the header. Trailing bytes are accepted.
+    pub fn new_checked(buffer: T) -> Result<Self, Error> {
+        let packet = Self::new_unchecked(buffer);
+        let data = packet.buffer.as_ref();
+        if data.len() < 4 {
+            return Err(Error::WrongNumberOfBytes);
+        }
+        check_bounded_len(data.len(), 4, NEIGHBOR_SIZE, packet.entry_count())?;
+        Ok(packet)
+    }
+
+    /// Consume the packet, returning the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    pub fn status(&self) -> Result<Status, Error> {
+        Status::try_from(self.buffer.as_ref()[0])
+    }
+
+    pub fn neighbors_total(&self) -> u8 {
+        self.buffer.as_ref()[1]
+    }
+
+    pub fn index(&self) -> u8 {
+        self.buffer.as_ref()[2]
+    }
+
+    /// Number of neighbor entries carried by this packet.
+    pub fn entry_count(&self) -> usize {
+        self.buffer.as_ref()[3] as usize
+    }
+
+    /// Decode the neighbor at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for `entry_count()`, or if the
+    /// entry holds a device type, rx-on-when-idle, relationship or
+    /// permit-joining value that does not decode.
+    pub fn neighbor(&self, index: usize) -> Neighbor {
+        assert!(index < self.entry_count());
+        let offset = 4 + (index * NEIGHBOR_SIZE);
+        let data = self.buffer.as_ref();
+        decode_neighbor(&data[offset..offset + NEIGHBOR_SIZE]).expect("neighbor entry does not decode")
+    }
+
+    /// Iterate over the neighbors without allocating.
+    pub fn neighbors(&self) -> NeighborIter<'_> {
+        BoundedEntries::new(
+            self.buffer.as_ref(),
+            4,
+            NEIGHBOR_SIZE,
+            self.entry_count(),
+            decode_neighbor,
+        )
+    }
+
+    /// Build the owning [`ManagementLinkQualityIndicatorResponse`]
+    /// representation from this packet.
+    pub fn to_repr(&self) -> Result<ManagementLinkQualityIndicatorResponse, Error> {
+        let data = self.buffer.as_ref();
+        let mut neighbors = Vec::with_capacity(self.entry_count());
+        for index in 0..self.entry_count() {
+            let offset = 4 + (index * NEIGHBOR_SIZE);
+            neighbors.push(decode_neighbor(&data[offset..offset + NEIGHBOR_SIZE])?);
+        }
+        Ok(ManagementLinkQualityIndicatorResponse {
+            status: self.status()?,
+            neighbors_total: self.neighbors_total(),
+            index: self.index(),
+            neighbors,
+        })
+    }
+}
+
 /// Network and IEEE address response
 ///
 #[derive(Clone, Debug, PartialEq)]
@@ -98,37 +237,56 @@ pub struct ManagementLinkQualityIndicatorResponse {
 impl Pack<ManagementLinkQualityIndicatorResponse, Error>
     for ManagementLinkQualityIndicatorResponse
 {
-    fn pack(&self, _data: &mut [u8]) -> Result<usize, Error> {
-        unimplemented!();
-    }
-
-    fn unpack(data: &[u8]) -> Result<(Self, usize), Error> {
-        if data.len() < 4 {
+    fn pack(&self, data: &mut [u8]) -> Result<usize, Error> {
+        if self.neighbors.len() > u8::MAX as usize {
             return Err(Error::WrongNumberOfBytes);
         }
-        let status = Status::try_from(data[0])?;
-        let neighbors_total = data[1];
-        let index = data[2];
-        let num_entries = data[3] as usize;
-        if data.len() < 4 + (num_entries * 22) {
+        if data.len() < 4 + (self.neighbors.len() * NEIGHBOR_SIZE) {
             return Err(Error::WrongNumberOfBytes);
         }
+        data[0] = u8::from(self.status);
+        data[1] = self.neighbors_total;
+        data[2] = self.index;
+        data[3] = self.neighbors.len() as u8;
         let mut offset = 4;
-        let mut neighbors: Vec<Neighbor> = Vec::with_capacity(num_entries);
-        for _ in 0..num_entries {
-            let (neighbor, used) = Neighbor::unpack(&data[offset..])?;
-            neighbors.push(neighbor);
-            offset += used;
+        for neighbor in self.neighbors.iter() {
+            offset += neighbor.pack(&mut data[offset..offset + NEIGHBOR_SIZE])?;
         }
-        Ok((
-            Self {
-                status,
-                neighbors_total,
-                index,
-                neighbors,
-            },
-            offset,
-        ))
+        Ok(offset)
+    }
+
+    fn unpack(data: &[u8]) -> Result<(Self, usize), Error> {
+        let packet = LinkQualityResponsePacket::new_checked(data)?;
+        let used = 4 + (packet.entry_count() * NEIGHBOR_SIZE);
+        Ok((packet.to_repr()?, used))
+    }
+}
+
+#[cfg(feature = "pretty-print")]
+impl PrettyPrint for Neighbor {
+    fn pretty_print(&self, indent: usize, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write_indent(indent, f)?;
+        writeln!(
+            f,
+            "relationship {:?} device type {:?} depth {} link quality {}",
+            self.relationship, self.device_type, self.depth, self.link_quality
+        )
+    }
+}
+
+#[cfg(feature = "pretty-print")]
+impl PrettyPrint for ManagementLinkQualityIndicatorResponse {
+    fn pretty_print(&self, indent: usize, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write_indent(indent, f)?;
+        writeln!(
+            f,
+            "LQI response status {:?} total {} index {}",
+            self.status, self.neighbors_total, self.index
+        )?;
+        for neighbor in &self.neighbors {
+            neighbor.pretty_print(indent + 2, f)?;
+        }
+        Ok(())
     }
 }
 
@@ -149,3 +307,125 @@ mod tests {
         assert_eq!(rsp.status, Status::Success);
     }
 }
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn arb_network_address() -> impl Strategy<Value = NetworkAddress> {
+        any::<u16>().prop_map(NetworkAddress::new)
+    }
+
+    fn arb_extended_address() -> impl Strategy<Value = ExtendedAddress> {
+        any::<u64>().prop_map(ExtendedAddress::new)
+    }
+
+    fn arb_device_type() -> impl Strategy<Value = DeviceType> {
+        prop_oneof![
+            Just(DeviceType::Coordinator),
+            Just(DeviceType::Router),
+            Just(DeviceType::EndDevice),
+            Just(DeviceType::Unknown),
+        ]
+    }
+
+    fn arb_rx_idle() -> impl Strategy<Value = RxOnWhenIdle> {
+        prop_oneof![
+            Just(RxOnWhenIdle::Off),
+            Just(RxOnWhenIdle::On),
+            Just(RxOnWhenIdle::Unknown),
+        ]
+    }
+
+    fn arb_relationship() -> impl Strategy<Value = Relationship> {
+        prop_oneof![
+            Just(Relationship::Parent),
+            Just(Relationship::Child),
+            Just(Relationship::Sibling),
+            Just(Relationship::NoneOfAbove),
+            Just(Relationship::PreviousChild),
+        ]
+    }
+
+    fn arb_permit_joining() -> impl Strategy<Value = PermitJoining> {
+        prop_oneof![
+            Just(PermitJoining::Yes),
+            Just(PermitJoining::No),
+            Just(PermitJoining::Unknown),
+        ]
+    }
+
+    fn arb_neighbor() -> impl Strategy<Value = Neighbor> {
+        (
+            arb_extended_address(),
+            arb_extended_address(),
+            arb_network_address(),
+            arb_device_type(),
+            arb_rx_idle(),
+            arb_relationship(),
+            arb_permit_joining(),
+            any::<u8>(),
+            any::<u8>(),
+        )
+            .prop_map(
+                |(
+                    pan_identifier,
+                    extended_address,
+                    network_address,
+                    device_type,
+                    rx_idle,
+                    relationship,
+                    permit_joining,
+                    depth,
+                    link_quality,
+                )| Neighbor {
+                    pan_identifier,
+                    extended_address,
+                    network_address,
+                    device_type,
+                    rx_idle,
+                    relationship,
+                    permit_joining,
+                    depth,
+                    link_quality,
+                },
+            )
+    }
+
+    fn arb_response() -> impl Strategy<Value = ManagementLinkQualityIndicatorResponse> {
+        (
+            any::<u8>(),
+            any::<u8>(),
+            proptest::collection::vec(arb_neighbor(), 0..8),
+        )
+            .prop_map(
+                |(neighbors_total, index, neighbors)| ManagementLinkQualityIndicatorResponse {
+                    status: Status::Success,
+                    neighbors_total,
+                    index,
+                    neighbors,
+                },
+            )
+    }
+
+    proptest! {
+        #[test]
+        fn neighbor_round_trips(neighbor in arb_neighbor()) {
+            let mut buffer = [0u8; NEIGHBOR_SIZE];
+            let used = neighbor.pack(&mut buffer).unwrap();
+            let (decoded, used2) = Neighbor::unpack(&buffer[..used]).unwrap();
+            prop_assert_eq!(used, used2);
+            prop_assert_eq!(neighbor, decoded);
+        }
+
+        #[test]
+        fn lqi_response_round_trips(response in arb_response()) {
+            let mut buffer = [0u8; 4 + 8 * NEIGHBOR_SIZE];
+            let used = response.pack(&mut buffer).unwrap();
+            let (decoded, used2) = ManagementLinkQualityIndicatorResponse::unpack(&buffer[..used]).unwrap();
+            prop_assert_eq!(used, used2);
+            prop_assert_eq!(response, decoded);
+        }
+    }
+}
@@ -1,5 +1,10 @@
+use zerocopy::{AsBytes, FromBytes, LayoutVerified, Unaligned};
+
 use crate::error::Error;
 use crate::pack::{Pack, PackFixed};
+use crate::packet_view::{check_bounded_len, BoundedEntries};
+#[cfg(feature = "pretty-print")]
+use crate::pretty_print::{write_indent, PrettyPrint};
 use crate::NetworkAddress;
 
 const INCOMING_COST_MASK: u8 = 0b0000_0111;
@@ -13,25 +18,35 @@ pub struct LinkStatusEntry {
     pub outgoing_cost: u8,
 }
 
+/// Byte-for-byte layout of a packed link status entry: a two-byte
+/// network address followed by the bit-packed incoming/outgoing cost
+/// nibbles. Deriving `FromBytes`/`AsBytes`/`Unaligned` lets `PackFixed`
+/// reinterpret a buffer in place instead of indexing it by hand.
+#[derive(FromBytes, AsBytes, Unaligned, Copy, Clone)]
+#[repr(C)]
+struct LinkStatusEntryLayout {
+    address: [u8; 2],
+    cost: u8,
+}
+
 impl PackFixed<LinkStatusEntry, Error> for LinkStatusEntry {
     fn pack(&self, data: &mut [u8]) -> Result<(), Error> {
-        if data.len() != LINK_STATUS_ENTRY_SIZE {
-            return Err(Error::WrongNumberOfBytes);
+        if self.incoming_cost >= 8 || self.outgoing_cost >= 8 {
+            return Err(Error::InvalidValue);
         }
-        assert!(self.incoming_cost < 16);
-        assert!(self.outgoing_cost < 16);
-        self.address.pack(&mut data[0..2])?;
-        data[2] = self.incoming_cost | self.outgoing_cost << 4;
+        let mut layout: LayoutVerified<&mut [u8], LinkStatusEntryLayout> =
+            LayoutVerified::new(data).ok_or(Error::WrongNumberOfBytes)?;
+        self.address.pack(&mut layout.address[..])?;
+        layout.cost = self.incoming_cost | (self.outgoing_cost << 4);
         Ok(())
     }
 
     fn unpack(data: &[u8]) -> Result<Self, Error> {
-        if data.len() != LINK_STATUS_ENTRY_SIZE {
-            return Err(Error::WrongNumberOfBytes);
-        }
-        let address = NetworkAddress::unpack(&data[0..2])?;
-        let incoming_cost = data[2] & INCOMING_COST_MASK;
-        let outgoing_cost = (data[2] & OUTGOING_COST_MASK) >> 4;
+        let layout: LayoutVerified<&[u8], LinkStatusEntryLayout> =
+            LayoutVerified::new(data).ok_or(Error::WrongNumberOfBytes)?;
+        let address = NetworkAddress::unpack(&layout.address[..])?;
+        let incoming_cost = layout.cost & INCOMING_COST_MASK;
+        let outgoing_cost = (layout.cost & OUTGOING_COST_MASK) >> 4;
         Ok(LinkStatusEntry {
             address,
             incoming_cost,
@@ -44,6 +59,92 @@ const NUMBER_OF_ENTRIES_MASK: u8 = 0b0001_1111;
 const FIRST_FRAME: u8 = 0b0010_0000;
 const LAST_FRAME: u8 = 0b0100_0000;
 
+/// A borrowed, zero-copy view over a link status payload.
+///
+/// Unlike [`LinkStatus`], this does not allocate a `Vec` of entries up
+/// front; [`entry`](LinkStatusPacket::entry) and
+/// [`entries`](LinkStatusPacket::entries) decode entries from the
+/// underlying buffer on demand.
+#[derive(Copy, Clone, Debug)]
+pub struct LinkStatusPacket<T: AsRef<[u8]>> {
+    buffer: T,
+}
+
+/// Allocation-free iterator over the entries of a [`LinkStatusPacket`].
+pub type LinkStatusEntryIter<'a> = BoundedEntries<'a, LinkStatusEntry>;
+
+impl<T: AsRef<[u8]>> LinkStatusPacket<T> {
+    /// Wrap `buffer` without checking that it holds a well-formed packet.
+    pub fn new_unchecked(buffer: T) -> Self {
+        Self { buffer }
+    }
+
+    /// Wrap `buffer`, checking that it is long enough to hold the entry
+    /// count encoded in the first byte. Trailing bytes are accepted.
+    pub fn new_checked(buffer: T) -> Result<Self, Error> {
+        let packet = Self::new_unchecked(buffer);
+        let data = packet.buffer.as_ref();
+        if data.is_empty() {
+            return Err(Error::WrongNumberOfBytes);
+        }
+        check_bounded_len(data.len(), 1, LINK_STATUS_ENTRY_SIZE, packet.entry_count())?;
+        Ok(packet)
+    }
+
+    /// Consume the packet, returning the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
+    pub fn first_frame(&self) -> bool {
+        let data = self.buffer.as_ref();
+        (data[0] & FIRST_FRAME) == FIRST_FRAME
+    }
+
+    pub fn last_frame(&self) -> bool {
+        let data = self.buffer.as_ref();
+        (data[0] & LAST_FRAME) == LAST_FRAME
+    }
+
+    /// Number of link status entries carried by this packet.
+    pub fn entry_count(&self) -> usize {
+        (self.buffer.as_ref()[0] & NUMBER_OF_ENTRIES_MASK) as usize
+    }
+
+    /// Decode the entry at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds for `entry_count()`.
+    pub fn entry(&self, index: usize) -> LinkStatusEntry {
+        assert!(index < self.entry_count());
+        let offset = 1 + (index * LINK_STATUS_ENTRY_SIZE);
+        let data = self.buffer.as_ref();
+        LinkStatusEntry::unpack(&data[offset..offset + LINK_STATUS_ENTRY_SIZE])
+            .expect("length already validated")
+    }
+
+    /// Iterate over the entries without allocating.
+    pub fn entries(&self) -> LinkStatusEntryIter<'_> {
+        BoundedEntries::new(
+            self.buffer.as_ref(),
+            1,
+            LINK_STATUS_ENTRY_SIZE,
+            self.entry_count(),
+            LinkStatusEntry::unpack,
+        )
+    }
+
+    /// Build the owning [`LinkStatus`] representation from this packet.
+    pub fn to_repr(&self) -> LinkStatus {
+        LinkStatus {
+            first_frame: self.first_frame(),
+            last_frame: self.last_frame(),
+            entries: self.entries().collect(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct LinkStatus {
     pub first_frame: bool,
@@ -70,30 +171,106 @@ impl Pack<LinkStatus, Error> for LinkStatus {
     }
 
     fn unpack(data: &[u8]) -> Result<(Self, usize), Error> {
-        if data.is_empty() {
-            return Err(Error::WrongNumberOfBytes);
-        }
-        let num_entries = (data[0] & NUMBER_OF_ENTRIES_MASK) as usize;
-        if data.len() < (1 + (num_entries * LINK_STATUS_ENTRY_SIZE)) {
-            return Err(Error::WrongNumberOfBytes);
-        }
-        let first_frame = (data[0] & FIRST_FRAME) == FIRST_FRAME;
-        let last_frame = (data[0] & LAST_FRAME) == LAST_FRAME;
-        let mut offset = 1;
-        let mut entries: Vec<LinkStatusEntry> = Vec::with_capacity(num_entries);
-        for _ in 0..num_entries {
-            let entry = LinkStatusEntry::unpack(&data[offset..offset + LINK_STATUS_ENTRY_SIZE])?;
-            entries.push(entry);
-            offset += LINK_STATUS_ENTRY_SIZE;
+        let packet = LinkStatusPacket::new_checked(data)?;
+        let used = 1 + (packet.entry_count() * LINK_STATUS_ENTRY_SIZE);
+        Ok((packet.to_repr(), used))
+    }
+}
+
+#[cfg(feature = "pretty-print")]
+impl PrettyPrint for LinkStatusEntry {
+    fn pretty_print(&self, indent: usize, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write_indent(indent, f)?;
+        writeln!(
+            f,
+            "address {:?} incoming cost {} outgoing cost {}",
+            self.address, self.incoming_cost, self.outgoing_cost
+        )
+    }
+}
+
+#[cfg(feature = "pretty-print")]
+impl PrettyPrint for LinkStatus {
+    fn pretty_print(&self, indent: usize, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write_indent(indent, f)?;
+        writeln!(
+            f,
+            "LinkStatus first frame {} last frame {}",
+            self.first_frame, self.last_frame
+        )?;
+        for entry in &self.entries {
+            entry.pretty_print(indent + 2, f)?;
         }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_unpack_max_entries_round_trip() {
+        let entries: Vec<LinkStatusEntry> = (0..31u16)
+            .map(|n| LinkStatusEntry {
+                address: NetworkAddress::new(n),
+                incoming_cost: (n % 8) as u8,
+                outgoing_cost: ((n + 1) % 8) as u8,
+            })
+            .collect();
+        let status = LinkStatus {
+            first_frame: true,
+            last_frame: true,
+            entries,
+        };
+        let mut buffer = [0u8; 1 + 31 * LINK_STATUS_ENTRY_SIZE];
+        let used = status.pack(&mut buffer).unwrap();
+        let (decoded, used2) = LinkStatus::unpack(&buffer[..used]).unwrap();
+        assert_eq!(used, used2);
+        assert_eq!(status, decoded);
+    }
+}
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn arb_network_address() -> impl Strategy<Value = NetworkAddress> {
+        any::<u16>().prop_map(NetworkAddress::new)
+    }
 
-        Ok((
-            LinkStatus {
+    fn arb_link_status_entry() -> impl Strategy<Value = LinkStatusEntry> {
+        (arb_network_address(), 0u8..8, 0u8..8).prop_map(
+            |(address, incoming_cost, outgoing_cost)| LinkStatusEntry {
+                address,
+                incoming_cost,
+                outgoing_cost,
+            },
+        )
+    }
+
+    fn arb_link_status() -> impl Strategy<Value = LinkStatus> {
+        (
+            any::<bool>(),
+            any::<bool>(),
+            proptest::collection::vec(arb_link_status_entry(), 0..32),
+        )
+            .prop_map(|(first_frame, last_frame, entries)| LinkStatus {
                 first_frame,
                 last_frame,
                 entries,
-            },
-            offset,
-        ))
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn link_status_round_trips(status in arb_link_status()) {
+            let mut buffer = [0u8; 1 + 31 * LINK_STATUS_ENTRY_SIZE];
+            let used = status.pack(&mut buffer).unwrap();
+            let (decoded, used2) = LinkStatus::unpack(&buffer[..used]).unwrap();
+            prop_assert_eq!(used, used2);
+            prop_assert_eq!(status, decoded);
+        }
     }
 }
@@ -0,0 +1,275 @@
+use crate::device_profile::link_quality::{DeviceType, Neighbor, Relationship};
+use crate::network::commands::link_status::LinkStatusEntry;
+use crate::NetworkAddress;
+
+/// Maximum number of neighbors tracked by a single `NeighborTable`.
+const MAX_NEIGHBORS: usize = 32;
+
+/// Time, in seconds, a neighbor may go without being refreshed before
+/// `housekeep()` considers it stale and evicts it.
+const NEIGHBOR_TIMEOUT: u32 = 120;
+
+/// A table that learns records from the network and ages them out over
+/// time.
+///
+/// The neighbor table and the network routing table share this shape:
+/// entries are merged in with `learn()`, read back with `lookup()`, and
+/// periodically swept for staleness with `housekeep()`.
+pub trait Table<K, V> {
+    /// Merge a record into the existing record for `key`, or insert it
+    /// if `key` is not yet known.
+    fn learn(&mut self, key: K, record: V);
+    /// Look up the current record for `key`, if any.
+    fn lookup(&self, key: K) -> Option<&V>;
+    /// Evict entries that have not been refreshed since before the
+    /// table's timeout.
+    fn housekeep(&mut self, now: u32);
+}
+
+/// Derive the incoming link cost from a link quality indicator.
+///
+/// Follows the Zigbee specification's mapping from the estimated
+/// reception probability, `p`, to a link cost: `cost = clamp(round(1 /
+/// p^4), 1, 7)`. `link_quality` is the raw LQI byte, normalized to the
+/// `0..1` range to stand in for `p`.
+pub fn link_cost_from_quality(link_quality: u8) -> u8 {
+    let p = f32::from(link_quality) / 255.0;
+    if p <= 0.0 {
+        return 7;
+    }
+    let cost = (1.0 / p.powi(4)).round();
+    if cost < 1.0 {
+        1
+    } else if cost > 7.0 {
+        7
+    } else {
+        cost as u8
+    }
+}
+
+/// Everything known about a single neighbor.
+///
+/// Each field is learned independently, from whichever frame last
+/// reported it (a link status entry reports costs, an LQI response
+/// reports relationship/device-type/depth), so fields are `Option` and
+/// a field left `None` in an incoming record is left untouched by
+/// [`Table::learn`] rather than clearing what was already known.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NeighborInfo {
+    pub address: NetworkAddress,
+    /// Cost of the link from the neighbor to us.
+    pub incoming_cost: Option<u8>,
+    /// Cost of the link from us to the neighbor, as reported by the
+    /// neighbor itself.
+    pub outgoing_cost: Option<u8>,
+    pub relationship: Option<Relationship>,
+    pub device_type: Option<DeviceType>,
+    pub depth: Option<u8>,
+    pub link_quality: Option<u8>,
+    last_seen: u32,
+}
+
+impl NeighborInfo {
+    fn new(address: NetworkAddress, now: u32) -> Self {
+        Self {
+            address,
+            incoming_cost: None,
+            outgoing_cost: None,
+            relationship: None,
+            device_type: None,
+            depth: None,
+            link_quality: None,
+            last_seen: now,
+        }
+    }
+
+    /// Merge the fields `other` actually carries into `self`, keeping
+    /// whatever `self` already knew for fields `other` leaves `None`.
+    fn merge(&mut self, other: &NeighborInfo) {
+        if other.incoming_cost.is_some() {
+            self.incoming_cost = other.incoming_cost;
+        }
+        if other.outgoing_cost.is_some() {
+            self.outgoing_cost = other.outgoing_cost;
+        }
+        if other.relationship.is_some() {
+            self.relationship = other.relationship;
+        }
+        if other.device_type.is_some() {
+            self.device_type = other.device_type;
+        }
+        if other.depth.is_some() {
+            self.depth = other.depth;
+        }
+        if other.link_quality.is_some() {
+            self.link_quality = other.link_quality;
+        }
+        self.last_seen = other.last_seen;
+    }
+
+    /// The cost of the best known path through this neighbor, combining
+    /// the incoming and outgoing link costs. Unknown costs are treated
+    /// as the worst possible cost, `7`.
+    pub fn cost(&self) -> u8 {
+        self.incoming_cost
+            .unwrap_or(7)
+            .saturating_add(self.outgoing_cost.unwrap_or(7))
+    }
+}
+
+/// Tracks the link state of a node's one-hop neighbors.
+#[derive(Clone, Debug)]
+pub struct NeighborTable {
+    entries: [Option<NeighborInfo>; MAX_NEIGHBORS],
+}
+
+impl Default for NeighborTable {
+    fn default() -> Self {
+        Self {
+            entries: [None; MAX_NEIGHBORS],
+        }
+    }
+}
+
+impl NeighborTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn index_of(&self, address: NetworkAddress) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|entry| matches!(entry, Some(info) if info.address == address))
+    }
+
+    /// Merge a link status entry, updating the incoming and outgoing
+    /// cost of the neighbor it describes.
+    pub fn learn_link_status(&mut self, entry: &LinkStatusEntry, now: u32) {
+        let mut record = NeighborInfo::new(entry.address, now);
+        record.incoming_cost = Some(entry.incoming_cost);
+        record.outgoing_cost = Some(entry.outgoing_cost);
+        self.learn(entry.address, record);
+    }
+
+    /// Merge a neighbor table entry from a management LQI response,
+    /// deriving the incoming cost from the reported link quality.
+    pub fn learn_neighbor(&mut self, neighbor: &Neighbor, now: u32) {
+        let mut record = NeighborInfo::new(neighbor.network_address, now);
+        record.relationship = Some(neighbor.relationship);
+        record.device_type = Some(neighbor.device_type);
+        record.depth = Some(neighbor.depth);
+        record.link_quality = Some(neighbor.link_quality);
+        record.incoming_cost = Some(link_cost_from_quality(neighbor.link_quality));
+        self.learn(neighbor.network_address, record);
+    }
+
+    /// The cost of the best known next-hop link to `address`, if the
+    /// neighbor is known.
+    pub fn best_cost_to(&self, address: NetworkAddress) -> Option<u8> {
+        self.lookup(address).map(NeighborInfo::cost)
+    }
+}
+
+impl Table<NetworkAddress, NeighborInfo> for NeighborTable {
+    /// When all `MAX_NEIGHBORS` slots are taken, a record for an
+    /// unknown `key` is dropped.
+    fn learn(&mut self, key: NetworkAddress, record: NeighborInfo) {
+        if let Some(index) = self.index_of(key) {
+            if let Some(existing) = self.entries[index].as_mut() {
+                existing.merge(&record);
+            }
+        } else if let Some(index) = self.entries.iter().position(Option::is_none) {
+            self.entries[index] = Some(record);
+        }
+    }
+
+    fn lookup(&self, key: NetworkAddress) -> Option<&NeighborInfo> {
+        self.entries
+            .iter()
+            .find_map(|entry| entry.as_ref().filter(|info| info.address == key))
+    }
+
+    fn housekeep(&mut self, now: u32) {
+        for entry in self.entries.iter_mut() {
+            if let Some(info) = entry {
+                if now.saturating_sub(info.last_seen) > NEIGHBOR_TIMEOUT {
+                    *entry = None;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn link_cost_formula_bounds() {
+        assert_eq!(link_cost_from_quality(0), 7);
+        assert_eq!(link_cost_from_quality(255), 1);
+    }
+
+    #[test]
+    fn learn_and_lookup_link_status() {
+        let mut table = NeighborTable::new();
+        let address = NetworkAddress::new(0x1234);
+        let entry = LinkStatusEntry {
+            address,
+            incoming_cost: 3,
+            outgoing_cost: 5,
+        };
+        table.learn_link_status(&entry, 10);
+        let info = table.lookup(address).unwrap();
+        assert_eq!(info.incoming_cost, Some(3));
+        assert_eq!(info.outgoing_cost, Some(5));
+        assert_eq!(table.best_cost_to(address), Some(8));
+    }
+
+    #[test]
+    fn learn_merges_instead_of_overwriting() {
+        let mut table = NeighborTable::new();
+        let address = NetworkAddress::new(0x1234);
+        let entry = LinkStatusEntry {
+            address,
+            incoming_cost: 3,
+            outgoing_cost: 5,
+        };
+        table.learn_link_status(&entry, 10);
+
+        let neighbor = Neighbor {
+            pan_identifier: crate::common::address::ExtendedAddress::new(0),
+            extended_address: crate::common::address::ExtendedAddress::new(0),
+            network_address: address,
+            device_type: DeviceType::Router,
+            rx_idle: crate::device_profile::link_quality::RxOnWhenIdle::On,
+            relationship: Relationship::Sibling,
+            permit_joining: crate::device_profile::link_quality::PermitJoining::Yes,
+            depth: 2,
+            link_quality: 200,
+        };
+        table.learn_neighbor(&neighbor, 20);
+
+        // Learning the neighbor record must not clear the link status
+        // costs learned earlier: a direct `Table::learn` call merges
+        // rather than blindly replacing the stored record.
+        let info = table.lookup(address).unwrap();
+        assert_eq!(info.outgoing_cost, Some(5));
+        assert_eq!(info.relationship, Some(Relationship::Sibling));
+        assert_eq!(info.depth, Some(2));
+    }
+
+    #[test]
+    fn housekeep_evicts_stale_neighbors() {
+        let mut table = NeighborTable::new();
+        let address = NetworkAddress::new(0x4321);
+        let entry = LinkStatusEntry {
+            address,
+            incoming_cost: 1,
+            outgoing_cost: 1,
+        };
+        table.learn_link_status(&entry, 0);
+        table.housekeep(NEIGHBOR_TIMEOUT + 1);
+        assert!(table.lookup(address).is_none());
+    }
+}
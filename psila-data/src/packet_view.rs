@@ -0,0 +1,70 @@
+//! Shared plumbing for zero-copy packet views over a fixed header
+//! followed by a variable number of fixed-size entries (link status
+//! entries, LQI response neighbors, and similar ZDP/NWK lists).
+
+use crate::error::Error;
+
+/// Check that `buffer_len` bytes are enough to hold `header_len` bytes
+/// of header followed by `count` entries of `entry_len` bytes each.
+pub(crate) fn check_bounded_len(
+    buffer_len: usize,
+    header_len: usize,
+    entry_len: usize,
+    count: usize,
+) -> Result<(), Error> {
+    if buffer_len < header_len {
+        return Err(Error::WrongNumberOfBytes);
+    }
+    if buffer_len < header_len + (count * entry_len) {
+        return Err(Error::WrongNumberOfBytes);
+    }
+    Ok(())
+}
+
+/// Allocation-free iterator over fixed-size entries that follow a
+/// packet header, decoding each one on demand with `decode`.
+///
+/// Public because it is returned from public packet view methods
+/// (e.g. `LinkStatusPacket::entries()`); only this crate can
+/// construct one, via the `pub(crate)` constructor below.
+pub struct BoundedEntries<'a, T> {
+    data: &'a [u8],
+    header_len: usize,
+    entry_len: usize,
+    index: usize,
+    count: usize,
+    decode: fn(&[u8]) -> Result<T, Error>,
+}
+
+impl<'a, T> BoundedEntries<'a, T> {
+    pub(crate) fn new(
+        data: &'a [u8],
+        header_len: usize,
+        entry_len: usize,
+        count: usize,
+        decode: fn(&[u8]) -> Result<T, Error>,
+    ) -> Self {
+        Self {
+            data,
+            header_len,
+            entry_len,
+            index: 0,
+            count,
+            decode,
+        }
+    }
+}
+
+impl<'a, T> Iterator for BoundedEntries<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+        let offset = self.header_len + (self.index * self.entry_len);
+        let entry = (self.decode)(&self.data[offset..offset + self.entry_len]).ok()?;
+        self.index += 1;
+        Some(entry)
+    }
+}
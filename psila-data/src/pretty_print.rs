@@ -0,0 +1,23 @@
+//! Human-readable rendering of decoded frames, for inspecting captured
+//! traffic.
+//!
+//! Gated behind the `pretty-print` feature so `no_std` builds that do
+//! not need `core::fmt::Display`-style machinery stay lean.
+
+use core::fmt;
+
+/// Implemented by types that can render themselves as an indented,
+/// human-readable tree, descending into nested payloads as needed.
+pub trait PrettyPrint {
+    /// Write a human-readable representation of `self` to `f`, indented
+    /// by `indent` spaces.
+    fn pretty_print(&self, indent: usize, f: &mut fmt::Formatter) -> fmt::Result;
+}
+
+/// Write `indent` spaces to `f`.
+pub(crate) fn write_indent(indent: usize, f: &mut fmt::Formatter) -> fmt::Result {
+    for _ in 0..indent {
+        write!(f, " ")?;
+    }
+    Ok(())
+}